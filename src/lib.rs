@@ -0,0 +1,25 @@
+mod aggregate;
+mod event;
+mod store;
+
+mod lock;
+mod mem_store;
+mod persist;
+mod query;
+
+#[cfg(test)]
+mod test_fixtures;
+
+#[doc(hidden)]
+pub mod doc;
+
+#[cfg(feature = "test-framework")]
+pub mod test;
+
+pub use crate::aggregate::*;
+pub use crate::event::*;
+pub use crate::lock::*;
+pub use crate::mem_store::*;
+pub use crate::persist::*;
+pub use crate::query::*;
+pub use crate::store::*;
@@ -0,0 +1,41 @@
+use async_trait::async_trait;
+
+use crate::aggregate::Aggregate;
+
+/// Marker trait implemented by guard types that release an exclusive lock when dropped.
+///
+/// Combined with [EventStoreLockGuard] this lets an [EventStore](crate::store::EventStore)
+/// or [AggregateContext](crate::store::AggregateContext) hand out a critical section for a
+/// single aggregate id without requiring callers to unlock explicitly.
+pub trait UnlockOnDrop: Send {}
+
+/// An exclusive lock on a single aggregate id, held for the duration of a
+/// load -> react -> persist cycle so that concurrent event batches for the same aggregate
+/// cannot interleave and corrupt a [Reactor](crate::query::Reactor)'s saga progress.
+///
+/// The lock is released automatically when the guard is dropped.
+pub struct EventStoreLockGuard {
+    _inner: Box<dyn UnlockOnDrop>,
+}
+
+impl EventStoreLockGuard {
+    /// Wraps an implementation-specific unlock guard, e.g. a per-id async mutex guard for
+    /// an in-memory store, or a row-level lock handle for a persistent one.
+    pub fn new(inner: Box<dyn UnlockOnDrop>) -> Self {
+        Self { _inner: inner }
+    }
+}
+
+/// Implemented by an [EventStore](crate::store::EventStore) or
+/// [AggregateContext](crate::store::AggregateContext) that can provide an exclusive
+/// critical section around a single aggregate id.
+///
+/// The framework acquires this lock around the load -> react -> persist cycle when
+/// dispatching a [Reactor](crate::query::Reactor), so sagas get a safe critical section
+/// without forcing users to serialize dispatch globally.
+#[async_trait]
+pub trait EventStoreLock<A: Aggregate> {
+    /// Acquires an exclusive lock on `aggregate_id`, released when the returned guard is
+    /// dropped.
+    async fn lock(&self, aggregate_id: &str) -> Result<EventStoreLockGuard, A::Error>;
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use tokio::sync::Mutex as AsyncMutex;
+
+use crate::aggregate::Aggregate;
+use crate::lock::{EventStoreLock, EventStoreLockGuard, UnlockOnDrop};
+
+/// The [AggregateContext](crate::store::AggregateContext) used by the default in-memory
+/// [MemStore].
+#[derive(Debug, Default)]
+pub struct MemStoreAggregateContext<A: Aggregate> {
+    _phantom: PhantomData<A>,
+}
+
+/// An in-memory [EventStore](crate::store::EventStore), used by
+/// [`TestFramework`](crate::test::TestFramework) and
+/// [`GenericTestFramework::using_mem_store`](crate::test::GenericTestFramework::using_mem_store)
+/// as the default store for aggregate tests.
+pub struct MemStore<A: Aggregate> {
+    locks: Arc<std::sync::Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+    _phantom: PhantomData<A>,
+}
+
+impl<A: Aggregate> Default for MemStore<A> {
+    fn default() -> Self {
+        Self {
+            locks: Arc::new(std::sync::Mutex::new(HashMap::new())),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Releases a [MemStore] per-aggregate-id lock when dropped, and evicts the aggregate
+/// id's entry from the lock table once nothing else is waiting on it, so the table
+/// doesn't grow forever as distinct aggregate ids are locked over a store's lifetime.
+struct MemStoreUnlock {
+    guard: Option<tokio::sync::OwnedMutexGuard<()>>,
+    mutex: Arc<AsyncMutex<()>>,
+    aggregate_id: String,
+    locks: Arc<std::sync::Mutex<HashMap<String, Arc<AsyncMutex<()>>>>>,
+}
+
+impl UnlockOnDrop for MemStoreUnlock {}
+
+impl Drop for MemStoreUnlock {
+    fn drop(&mut self) {
+        // Release the lock itself first, so the only strong references left to inspect
+        // below are the lock table's own entry and our `mutex` field.
+        self.guard.take();
+        let mut locks = self.locks.lock().unwrap();
+        if Arc::strong_count(&self.mutex) <= 2 {
+            locks.remove(&self.aggregate_id);
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Aggregate> EventStoreLock<A> for MemStore<A> {
+    /// Acquires the per-aggregate-id async mutex for `aggregate_id`, creating it on first
+    /// use, and returns a guard that releases it on drop.
+    async fn lock(&self, aggregate_id: &str) -> Result<EventStoreLockGuard, A::Error> {
+        let mutex = {
+            let mut locks = self.locks.lock().unwrap();
+            locks
+                .entry(aggregate_id.to_string())
+                .or_insert_with(|| Arc::new(AsyncMutex::new(())))
+                .clone()
+        };
+        let guard = mutex.clone().lock_owned().await;
+        Ok(EventStoreLockGuard::new(Box::new(MemStoreUnlock {
+            guard: Some(guard),
+            mutex,
+            aggregate_id: aggregate_id.to_string(),
+            locks: self.locks.clone(),
+        })))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::test_fixtures::TestAggregate;
+
+    #[tokio::test]
+    async fn lock_serializes_access_to_the_same_aggregate_id() {
+        let store = Arc::new(MemStore::<TestAggregate>::default());
+        let first_guard = store.lock("agg-1").await.unwrap();
+
+        let store_clone = store.clone();
+        let second_lock = tokio::spawn(async move { store_clone.lock("agg-1").await.is_ok() });
+
+        // Give the spawned task a chance to run and block on the held lock.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!second_lock.is_finished());
+
+        drop(first_guard);
+        let acquired = tokio::time::timeout(Duration::from_millis(200), second_lock)
+            .await
+            .expect("second lock should acquire once the first is released")
+            .unwrap();
+        assert!(acquired);
+    }
+
+    #[tokio::test]
+    async fn lock_does_not_block_unrelated_aggregate_ids() {
+        let store = MemStore::<TestAggregate>::default();
+        let _first_guard = store.lock("agg-1").await.unwrap();
+
+        let second = tokio::time::timeout(Duration::from_millis(50), store.lock("agg-2")).await;
+        assert!(second.is_ok());
+    }
+
+    #[tokio::test]
+    async fn lock_table_entry_is_evicted_once_unused() {
+        let store = MemStore::<TestAggregate>::default();
+        let guard = store.lock("agg-1").await.unwrap();
+        assert_eq!(store.locks.lock().unwrap().len(), 1);
+
+        drop(guard);
+        assert_eq!(
+            store.locks.lock().unwrap().len(),
+            0,
+            "releasing the only lock on an aggregate id should evict its table entry"
+        );
+    }
+}
@@ -0,0 +1,415 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+use crate::aggregate::Aggregate;
+use crate::event::EventEnvelope;
+use crate::query::{Query, QueryKind, View};
+
+/// Identity and version metadata tracked alongside a persisted [View].
+///
+/// The `version` is maintained by the [ViewRepository] implementation and is expected to
+/// increase by exactly one on every successful write, so it can double as a
+/// compare-and-set token for detecting concurrent updates.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ViewContext {
+    /// The unique identifier of the view, usually matching the id of the aggregate it was built from.
+    pub view_id: String,
+    /// The version the view was loaded (or initialized) at.
+    pub version: i64,
+}
+
+impl ViewContext {
+    /// Creates a new `ViewContext` for the given view id and version.
+    pub fn new(view_id: String, version: i64) -> Self {
+        Self { view_id, version }
+    }
+}
+
+/// An error returned while loading or persisting a [View] through a [ViewRepository].
+#[derive(Debug)]
+pub enum PersistenceError {
+    /// Another writer persisted a newer version of the view than the one the caller loaded.
+    /// The caller should reload the view and re-apply its update rather than overwrite it.
+    OptimisticLock,
+    /// An error occurred connecting to, or querying, the underlying view store.
+    ConnectionError(Box<dyn std::error::Error + Send + Sync>),
+    /// An error occurred serializing or deserializing the view.
+    SerializationError(Box<dyn std::error::Error + Send + Sync>),
+}
+
+impl fmt::Display for PersistenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PersistenceError::OptimisticLock => {
+                write!(
+                    f,
+                    "the view was updated by another writer since it was loaded"
+                )
+            }
+            PersistenceError::ConnectionError(err) => write!(f, "{err}"),
+            PersistenceError::SerializationError(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl std::error::Error for PersistenceError {}
+
+/// Provides persistence for a [View], keyed by the id of the aggregate it represents.
+///
+/// Implementations are responsible for storing the view together with its [ViewContext]
+/// so that the version can be used to guard against lost updates.
+#[async_trait]
+pub trait ViewRepository<V, A>: Send + Sync
+where
+    V: View<A>,
+    A: Aggregate,
+{
+    /// Returns the view for the given id, or `None` if it has not yet been persisted.
+    async fn load(&self, view_id: &str) -> Result<Option<V>, PersistenceError>;
+
+    /// Returns the view along with the [ViewContext] it was loaded with, or `None` if it
+    /// has not yet been persisted.
+    async fn load_with_context(
+        &self,
+        view_id: &str,
+    ) -> Result<Option<(V, ViewContext)>, PersistenceError>;
+
+    /// Persists the view at `context.version + 1`.
+    ///
+    /// `context` is the base version the caller loaded the view at (via [load](ViewRepository::load)
+    /// or [load_with_context](ViewRepository::load_with_context), or version `0` for a view
+    /// being created for the first time). Implementations must compare `context.version`
+    /// against the version currently persisted for `context.view_id` and return
+    /// [PersistenceError::OptimisticLock] rather than overwrite a view that has moved on,
+    /// e.g. because another dispatch for the same aggregate raced this one; only on a match
+    /// should the view be written at `context.version + 1`.
+    async fn update_view(&self, view: V, context: ViewContext) -> Result<(), PersistenceError>;
+}
+
+/// Determines how a [GenericQuery] responds when [ViewRepository::update_view] reports
+/// an [PersistenceError::OptimisticLock] conflict.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GenericQueryConflictPolicy {
+    /// Surface the conflict to the configured error handler and give up on this dispatch.
+    ErrorOnConflict,
+    /// Reload the latest view, re-apply the current event batch on top of it, and retry
+    /// the write once. Useful when re-running the fold is cheaper than failing the dispatch.
+    RebuildFromRepository,
+}
+
+impl Default for GenericQueryConflictPolicy {
+    fn default() -> Self {
+        Self::ErrorOnConflict
+    }
+}
+
+/// A [Query] that loads, folds events into, and persists a [View] through a
+/// [ViewRepository], so read models no longer need their own hand-written
+/// load/apply/store loop.
+pub struct GenericQuery<R, V, A>
+where
+    R: ViewRepository<V, A>,
+    V: View<A>,
+    A: Aggregate,
+{
+    view_repository: R,
+    error_handler: Option<Box<dyn Fn(PersistenceError) + Send + Sync>>,
+    conflict_policy: GenericQueryConflictPolicy,
+    _phantom: PhantomData<(V, A)>,
+}
+
+impl<R, V, A> GenericQuery<R, V, A>
+where
+    R: ViewRepository<V, A>,
+    V: View<A>,
+    A: Aggregate,
+{
+    /// Creates a new `GenericQuery` backed by the provided [ViewRepository].
+    pub fn new(view_repository: R) -> Self {
+        Self {
+            view_repository,
+            error_handler: None,
+            conflict_policy: GenericQueryConflictPolicy::default(),
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Supplies a handler invoked whenever loading or persisting the view fails, since
+    /// [Query::dispatch] has no return value of its own to report errors through.
+    pub fn use_error_handler(
+        &mut self,
+        error_handler: Box<dyn Fn(PersistenceError) + Send + Sync>,
+    ) {
+        self.error_handler = Some(error_handler);
+    }
+
+    /// Sets the policy used to respond to an [PersistenceError::OptimisticLock] conflict
+    /// on [ViewRepository::update_view]. Defaults to [GenericQueryConflictPolicy::ErrorOnConflict].
+    pub fn use_conflict_policy(&mut self, conflict_policy: GenericQueryConflictPolicy) {
+        self.conflict_policy = conflict_policy;
+    }
+
+    fn handle_error(&self, error: PersistenceError) {
+        if let Some(handler) = &self.error_handler {
+            handler(error);
+        }
+    }
+
+    /// Reloads the latest view, re-applies `events` on top of it, and attempts the write
+    /// once more; used to recover from an [PersistenceError::OptimisticLock] conflict
+    /// under [GenericQueryConflictPolicy::RebuildFromRepository].
+    async fn rebuild_and_retry(&self, aggregate_id: &str, events: &[EventEnvelope<A>]) {
+        let (mut view, context) = match self.view_repository.load_with_context(aggregate_id).await {
+            Ok(Some(view_with_context)) => view_with_context,
+            Ok(None) => (V::default(), ViewContext::new(aggregate_id.to_string(), 0)),
+            Err(err) => return self.handle_error(err),
+        };
+        for event in events {
+            view.update(event);
+        }
+        if let Err(err) = self.view_repository.update_view(view, context).await {
+            self.handle_error(err);
+        }
+    }
+}
+
+#[async_trait]
+impl<R, V, A> Query<A> for GenericQuery<R, V, A>
+where
+    R: ViewRepository<V, A>,
+    V: View<A>,
+    A: Aggregate,
+{
+    async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<A>]) {
+        let (mut view, context) = match self.view_repository.load_with_context(aggregate_id).await {
+            Ok(Some(view_with_context)) => view_with_context,
+            Ok(None) => (V::default(), ViewContext::new(aggregate_id.to_string(), 0)),
+            Err(err) => return self.handle_error(err),
+        };
+        for event in events {
+            view.update(event);
+        }
+        match self.view_repository.update_view(view, context).await {
+            Ok(()) => {}
+            Err(PersistenceError::OptimisticLock)
+                if self.conflict_policy == GenericQueryConflictPolicy::RebuildFromRepository =>
+            {
+                self.rebuild_and_retry(aggregate_id, events).await;
+            }
+            Err(err) => self.handle_error(err),
+        }
+    }
+
+    /// A `GenericQuery` updates its view atomically with the commit it is folding, so it
+    /// always runs [QueryKind::Synchronous].
+    fn kind(&self) -> QueryKind {
+        QueryKind::Synchronous
+    }
+}
+
+/// A [ViewRepository] backed by a `HashMap`, used by
+/// [`GenericTestFramework::then`](crate::test::AggregateResultValidator::then) and suitable
+/// for any other caller that doesn't need the view to survive past the process.
+pub struct InMemoryViewRepository<V, A>
+where
+    V: View<A>,
+    A: Aggregate,
+{
+    views: Mutex<HashMap<String, (V, ViewContext)>>,
+    _phantom: PhantomData<A>,
+}
+
+impl<V, A> Default for InMemoryViewRepository<V, A>
+where
+    V: View<A>,
+    A: Aggregate,
+{
+    fn default() -> Self {
+        Self {
+            views: Mutex::new(HashMap::new()),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<V, A> ViewRepository<V, A> for InMemoryViewRepository<V, A>
+where
+    V: View<A> + Clone,
+    A: Aggregate,
+{
+    async fn load(&self, view_id: &str) -> Result<Option<V>, PersistenceError> {
+        Ok(self
+            .views
+            .lock()
+            .unwrap()
+            .get(view_id)
+            .map(|(view, _)| view.clone()))
+    }
+
+    async fn load_with_context(
+        &self,
+        view_id: &str,
+    ) -> Result<Option<(V, ViewContext)>, PersistenceError> {
+        Ok(self.views.lock().unwrap().get(view_id).cloned())
+    }
+
+    async fn update_view(&self, view: V, context: ViewContext) -> Result<(), PersistenceError> {
+        let mut views = self.views.lock().unwrap();
+        let persisted_version = views
+            .get(&context.view_id)
+            .map(|(_, persisted_context)| persisted_context.version)
+            .unwrap_or(0);
+        if persisted_version != context.version {
+            return Err(PersistenceError::OptimisticLock);
+        }
+        let next_context = ViewContext::new(context.view_id.clone(), context.version + 1);
+        views.insert(context.view_id.clone(), (view, next_context));
+        Ok(())
+    }
+}
+
+/// Forwards to the wrapped repository, so a [ViewRepository] can be shared between a
+/// [GenericQuery] and the test assertion that checks what it persisted (e.g. an
+/// `Arc<InMemoryViewRepository<_, _>>`).
+#[async_trait]
+impl<V, A, T> ViewRepository<V, A> for Arc<T>
+where
+    V: View<A>,
+    A: Aggregate,
+    T: ViewRepository<V, A>,
+{
+    async fn load(&self, view_id: &str) -> Result<Option<V>, PersistenceError> {
+        T::load(self, view_id).await
+    }
+
+    async fn load_with_context(
+        &self,
+        view_id: &str,
+    ) -> Result<Option<(V, ViewContext)>, PersistenceError> {
+        T::load_with_context(self, view_id).await
+    }
+
+    async fn update_view(&self, view: V, context: ViewContext) -> Result<(), PersistenceError> {
+        T::update_view(self, view, context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::test_fixtures::{envelope, TestAggregate, TestEvent};
+
+    #[derive(Debug, Default, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct TestView {
+        events: Vec<TestEvent>,
+    }
+
+    impl View<TestAggregate> for TestView {
+        fn update(&mut self, event: &EventEnvelope<TestAggregate>) {
+            self.events.push(event.payload.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn generic_query_creates_then_updates_a_view() {
+        let repository = Arc::new(InMemoryViewRepository::<TestView, TestAggregate>::default());
+        let query = GenericQuery::new(repository.clone());
+
+        query
+            .dispatch(
+                "agg-1",
+                &[envelope("agg-1", 1, TestEvent::Recorded("a".into()))],
+            )
+            .await;
+        let (view, context) = repository
+            .load_with_context("agg-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(view.events, vec![TestEvent::Recorded("a".into())]);
+        assert_eq!(context.version, 1);
+
+        query
+            .dispatch(
+                "agg-1",
+                &[envelope("agg-1", 2, TestEvent::Recorded("b".into()))],
+            )
+            .await;
+        let (view, context) = repository
+            .load_with_context("agg-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            view.events,
+            vec![
+                TestEvent::Recorded("a".into()),
+                TestEvent::Recorded("b".into())
+            ]
+        );
+        assert_eq!(context.version, 2);
+    }
+
+    #[tokio::test]
+    async fn update_view_rejects_a_stale_context() {
+        let repository = InMemoryViewRepository::<TestView, TestAggregate>::default();
+        let context = ViewContext::new("agg-1".to_string(), 0);
+        repository
+            .update_view(TestView::default(), context.clone())
+            .await
+            .unwrap();
+
+        // `context` still claims version 0, but the repository already moved to version 1.
+        let stale_view = TestView {
+            events: vec![TestEvent::Recorded("stale".into())],
+        };
+        let result = repository.update_view(stale_view, context).await;
+        assert!(matches!(result, Err(PersistenceError::OptimisticLock)));
+    }
+
+    #[tokio::test]
+    async fn rebuild_and_retry_reloads_the_latest_view_before_writing() {
+        let repository = Arc::new(InMemoryViewRepository::<TestView, TestAggregate>::default());
+        // Seed a view at version 1, simulating a write that raced the dispatch below.
+        repository
+            .update_view(
+                TestView {
+                    events: vec![TestEvent::Recorded("raced".into())],
+                },
+                ViewContext::new("agg-1".to_string(), 0),
+            )
+            .await
+            .unwrap();
+
+        let query = GenericQuery::new(repository.clone());
+        // Exercises the conflict-recovery path directly, the same one `dispatch` falls
+        // back to after an `OptimisticLock` from `update_view` under
+        // `GenericQueryConflictPolicy::RebuildFromRepository`.
+        query
+            .rebuild_and_retry(
+                "agg-1",
+                &[envelope("agg-1", 2, TestEvent::Recorded("new".into()))],
+            )
+            .await;
+
+        let (view, context) = repository
+            .load_with_context("agg-1")
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            view.events,
+            vec![
+                TestEvent::Recorded("raced".into()),
+                TestEvent::Recorded("new".into())
+            ]
+        );
+        assert_eq!(context.version, 2);
+    }
+}
@@ -19,6 +19,30 @@ use crate::store::AggregateContext;
 pub trait Query<A: Aggregate>: Send + Sync {
     /// Events will be dispatched here immediately after being committed.
     async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<A>]);
+
+    /// Determines when this query is dispatched relative to the event commit.
+    ///
+    /// Defaults to [QueryKind::Eventual] so existing queries keep today's
+    /// fire-and-forget-after-commit behavior; override to return
+    /// [QueryKind::Synchronous] for a query that must update its read model atomically
+    /// with the commit.
+    fn kind(&self) -> QueryKind {
+        QueryKind::Eventual
+    }
+}
+
+/// Determines when a [Query] is dispatched relative to the commit of the events it is
+/// handling, making the ordering and failure semantics of a query explicit rather than
+/// implicitly "fire and forget after commit".
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QueryKind {
+    /// Dispatched inside the same transaction as the event commit, so a failure rolls
+    /// back the commit. Suitable for queries that must keep a read model atomically
+    /// consistent with the events that produced it.
+    Synchronous,
+    /// Dispatched after the commit has already succeeded, and is allowed to fail or
+    /// retry independently, e.g. forwarding events to a messaging service.
+    Eventual,
 }
 
 /// A `View` represents a materialized view, generally serialized for persistence, that is updated by a query.
@@ -0,0 +1,304 @@
+use std::marker::PhantomData;
+
+use crate::aggregate::Aggregate;
+use crate::event::EventEnvelope;
+use crate::lock::EventStoreLock;
+use crate::persist::ViewRepository;
+use crate::query::{Query, QueryKind, Reactor, View};
+use crate::store::{AggregateContext, EventStore};
+
+/// Returned by [`GenericTestFramework::given`](crate::test::GenericTestFramework::given) and
+/// [`GenericTestFramework::given_no_previous_events`](crate::test::GenericTestFramework::given_no_previous_events),
+/// this drives a command against the aggregate built from those previous events.
+pub struct AggregateTestExecutor<A, AC, S>
+where
+    A: Aggregate,
+    AC: AggregateContext<A> + Send + Sync,
+    S: EventStore<A, AC = AC>,
+{
+    events: Vec<A::Event>,
+    service: A::Services,
+    queries: Vec<Box<dyn Query<A>>>,
+    reactors: Vec<Box<dyn Reactor<A, AC>>>,
+    context_store: Option<(AC, S)>,
+}
+
+impl<A, AC, S> AggregateTestExecutor<A, AC, S>
+where
+    A: Aggregate,
+    AC: AggregateContext<A> + Send + Sync,
+    S: EventStore<A, AC = AC>,
+{
+    pub(crate) fn new(
+        events: Vec<A::Event>,
+        service: A::Services,
+        queries: Vec<Box<dyn Query<A>>>,
+        reactors: Vec<Box<dyn Reactor<A, AC>>>,
+        context_store: Option<(AC, S)>,
+    ) -> Self {
+        Self {
+            events,
+            service,
+            queries,
+            reactors,
+            context_store,
+        }
+    }
+
+    /// Applies the previous events to a default aggregate and sends the command, capturing
+    /// the result for assertion by [`AggregateResultValidator`].
+    pub async fn when(self, command: A::Command) -> AggregateResultValidator<A, AC, S> {
+        let previous_event_count = self.events.len();
+        let mut aggregate = A::default();
+        for event in self.events {
+            aggregate.apply(event);
+        }
+        let result = aggregate.handle(command, &self.service).await;
+        AggregateResultValidator {
+            result,
+            previous_event_count,
+            service: self.service,
+            queries: self.queries,
+            reactors: self.reactors,
+            context_store: self.context_store,
+        }
+    }
+}
+
+/// Asserts on the outcome of the command sent via
+/// [`AggregateTestExecutor::when`].
+pub struct AggregateResultValidator<A, AC, S>
+where
+    A: Aggregate,
+    AC: AggregateContext<A> + Send + Sync,
+    S: EventStore<A, AC = AC>,
+{
+    result: Result<Vec<A::Event>, A::Error>,
+    previous_event_count: usize,
+    service: A::Services,
+    queries: Vec<Box<dyn Query<A>>>,
+    reactors: Vec<Box<dyn Reactor<A, AC>>>,
+    context_store: Option<(AC, S)>,
+}
+
+impl<A, AC, S> AggregateResultValidator<A, AC, S>
+where
+    A: Aggregate,
+    AC: AggregateContext<A> + Send + Sync,
+    S: EventStore<A, AC = AC>,
+{
+    /// Asserts that the command produced exactly the given events.
+    pub fn then_expect_events(self, expected_events: Vec<A::Event>) {
+        let events = self.result.expect("expected command to succeed");
+        assert_eq!(
+            events, expected_events,
+            "unexpected events produced by command"
+        );
+    }
+
+    /// Asserts that the command failed with an error matching the given message.
+    pub fn then_expect_error_message(self, error_message: &str) {
+        let error = self.result.expect_err("expected command to fail");
+        assert_eq!(error.to_string(), error_message, "unexpected error message");
+    }
+
+    /// Takes the command's result, builds the envelopes for its events (numbered onward
+    /// from `previous_event_count`, so they continue the aggregate's existing history
+    /// instead of restarting at sequence 1), and dispatches them to every registered query.
+    async fn take_result_and_dispatch_queries(
+        &mut self,
+        aggregate_id: &str,
+    ) -> Vec<EventEnvelope<A>> {
+        let events = std::mem::replace(&mut self.result, Ok(Vec::new()))
+            .expect("expected command to succeed before dispatching queries");
+        let envelopes = build_envelopes(aggregate_id, self.previous_event_count, events);
+        self.dispatch_queries(aggregate_id, &envelopes).await;
+        envelopes
+    }
+
+    async fn dispatch_queries(&self, aggregate_id: &str, envelopes: &[EventEnvelope<A>]) {
+        for query in self
+            .queries
+            .iter()
+            .filter(|query| query.kind() == QueryKind::Synchronous)
+        {
+            query.dispatch(aggregate_id, envelopes).await;
+        }
+        for query in self
+            .queries
+            .iter()
+            .filter(|query| query.kind() == QueryKind::Eventual)
+        {
+            query.dispatch(aggregate_id, envelopes).await;
+        }
+    }
+
+    async fn dispatch_reactors(
+        &self,
+        aggregate_id: &str,
+        envelopes: &[EventEnvelope<A>],
+    ) -> Vec<A::Event> {
+        let mut reactor_events = Vec::new();
+        if self.reactors.is_empty() {
+            return reactor_events;
+        }
+        let (context, _store) = self.context_store.as_ref().expect(
+            "reactors require an event store; call using_mem_store/using_context_and_store \
+             before and_reactor",
+        );
+        for reactor in &self.reactors {
+            let events = reactor
+                .react(context, aggregate_id, &self.service, envelopes)
+                .await
+                .expect("reactor failed to react");
+            reactor_events.extend(events);
+        }
+        reactor_events
+    }
+
+    /// Folds the events produced by the command through every query registered on the
+    /// test framework, then returns a [`ViewAssertion`] for checking the resulting state
+    /// of a view loaded through `view_repository`.
+    ///
+    /// Queries are dispatched in two ordered passes, honoring [`Query::kind`]:
+    /// every [`QueryKind::Synchronous`] query runs first, mirroring that it would share
+    /// the event commit's transaction and is expected to have applied before anything
+    /// else observes the commit; since `dispatch` has no `Result` of its own, a failing
+    /// assertion inside a synchronous query panics here and the [`QueryKind::Eventual`]
+    /// pass below never runs, standing in for the commit rollback a real transactional
+    /// store would perform. [`QueryKind::Eventual`] queries then run afterward and may
+    /// fail independently without affecting the synchronous pass that already completed.
+    /// The store-side half of this contract (actually wrapping the commit in a
+    /// transaction) belongs to a real `EventStore` and is outside this in-memory test
+    /// executor.
+    ///
+    /// Every [Reactor] registered via
+    /// [`GenericTestFramework::and_reactor`](crate::test::GenericTestFramework::and_reactor)
+    /// is then invoked with the same events, with no store-level locking around the pass;
+    /// use [`then_with_reactor_lock`](Self::then_with_reactor_lock) instead when `S`
+    /// implements [`EventStoreLock`] and the critical section matters to the test. Either
+    /// way, the follow-up events every reactor returns are collected, in registration
+    /// order, onto the returned [`ViewAssertion`] so a test can assert on them with
+    /// [`ViewAssertion::expect_reactor_events`] the same way a
+    /// [`RecordingReactor`](crate::test::RecordingReactor) asserts its own call count.
+    ///
+    /// The same `view_repository` instance (or a handle sharing its storage, e.g. an
+    /// `Arc`-wrapped in-memory repository) should back the query under test so that the
+    /// write performed during dispatch is visible to the assertion.
+    pub async fn then<'a, R, V>(
+        mut self,
+        aggregate_id: &str,
+        view_repository: &'a R,
+    ) -> ViewAssertion<'a, R, V, A>
+    where
+        R: ViewRepository<V, A>,
+        V: View<A>,
+    {
+        let envelopes = self.take_result_and_dispatch_queries(aggregate_id).await;
+        let reactor_events = self.dispatch_reactors(aggregate_id, &envelopes).await;
+        ViewAssertion {
+            view_repository,
+            reactor_events,
+            _phantom: PhantomData,
+        }
+    }
+
+    /// Like [`then`](Self::then), but additionally acquires an exclusive per-aggregate
+    /// lock from the event store around the reactor dispatch pass, for a store that
+    /// implements [`EventStoreLock`]. This mirrors the load -> react -> persist critical
+    /// section a production saga dispatcher would hold, so a test can confirm a reactor
+    /// behaves correctly under that lock.
+    pub async fn then_with_reactor_lock<'a, R, V>(
+        mut self,
+        aggregate_id: &str,
+        view_repository: &'a R,
+    ) -> ViewAssertion<'a, R, V, A>
+    where
+        R: ViewRepository<V, A>,
+        V: View<A>,
+        S: EventStoreLock<A>,
+    {
+        let envelopes = self.take_result_and_dispatch_queries(aggregate_id).await;
+        let reactor_events = if self.reactors.is_empty() {
+            Vec::new()
+        } else {
+            let (_, store) = self.context_store.as_ref().expect(
+                "reactors require an event store; call using_mem_store/using_context_and_store \
+                 before and_reactor",
+            );
+            let _lock = store
+                .lock(aggregate_id)
+                .await
+                .expect("failed to acquire aggregate lock for reactor dispatch");
+            self.dispatch_reactors(aggregate_id, &envelopes).await
+        };
+        ViewAssertion {
+            view_repository,
+            reactor_events,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+/// Builds envelopes for newly produced `events`, numbered onward from
+/// `previous_event_count` so they continue the aggregate's existing history instead of
+/// restarting at sequence 1.
+fn build_envelopes<A: Aggregate>(
+    aggregate_id: &str,
+    previous_event_count: usize,
+    events: Vec<A::Event>,
+) -> Vec<EventEnvelope<A>> {
+    events
+        .into_iter()
+        .enumerate()
+        .map(|(index, payload)| EventEnvelope {
+            aggregate_id: aggregate_id.to_string(),
+            sequence: previous_event_count + index + 1,
+            payload,
+            metadata: Default::default(),
+        })
+        .collect()
+}
+
+/// Asserts on the state of a view, and on any follow-up events reactors returned, after a
+/// [`AggregateResultValidator::then`] dispatch.
+pub struct ViewAssertion<'a, R, V, A>
+where
+    R: ViewRepository<V, A>,
+    V: View<A>,
+    A: Aggregate,
+{
+    view_repository: &'a R,
+    reactor_events: Vec<A::Event>,
+    _phantom: PhantomData<(V, A)>,
+}
+
+impl<'a, R, V, A> ViewAssertion<'a, R, V, A>
+where
+    R: ViewRepository<V, A>,
+    V: View<A> + PartialEq,
+    A: Aggregate,
+{
+    /// Loads the view for `view_id` through the repository and asserts it matches `expected`.
+    pub async fn expect_view(&self, view_id: &str, expected: V) {
+        let actual = self
+            .view_repository
+            .load(view_id)
+            .await
+            .expect("failed to load view")
+            .expect("view was not persisted");
+        assert!(actual == expected, "view did not match expected state");
+    }
+
+    /// Asserts that the events returned by every registered [Reactor], concatenated in
+    /// registration order, match `expected`.
+    pub fn expect_reactor_events(&self, expected: &[A::Event])
+    where
+        A::Event: PartialEq + std::fmt::Debug,
+    {
+        assert_eq!(
+            &self.reactor_events, expected,
+            "unexpected reactor follow-up events"
+        );
+    }
+}
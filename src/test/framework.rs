@@ -80,6 +80,8 @@ where
 {
     /// Add a query into the current test framework. An event store must be defined
     /// before providing pre-conditions with ([given_no_previous_events] / [given]).
+    /// The query's [`Query::kind`](crate::query::Query::kind) determines whether it is
+    /// dispatched synchronously with the commit or afterward as an eventual publisher.
     pub fn and_query(self, query: Box<dyn Query<A>>) -> Self {
         let service = self.service;
         let mut queries = self.queries;
@@ -135,7 +137,13 @@ where
     /// ```
     #[must_use]
     pub fn given_no_previous_events(self) -> AggregateTestExecutor<A, AC, S> {
-        AggregateTestExecutor::new(Vec::new(), self.service, self.queries, self.context_store)
+        AggregateTestExecutor::new(
+            Vec::new(),
+            self.service,
+            self.queries,
+            self.reactors,
+            self.context_store,
+        )
     }
     /// Initiates an aggregate test with a collection of previous events.
     ///
@@ -148,6 +156,12 @@ where
     /// ```
     #[must_use]
     pub fn given(self, events: Vec<A::Event>) -> AggregateTestExecutor<A, AC, S> {
-        AggregateTestExecutor::new(events, self.service, self.queries, self.context_store)
+        AggregateTestExecutor::new(
+            events,
+            self.service,
+            self.queries,
+            self.reactors,
+            self.context_store,
+        )
     }
 }
@@ -0,0 +1,7 @@
+mod executor;
+mod framework;
+mod recording;
+
+pub use executor::{AggregateResultValidator, AggregateTestExecutor, ViewAssertion};
+pub use framework::{GenericTestFramework, TestFramework};
+pub use recording::{RecordingQuery, RecordingReactor};
@@ -0,0 +1,212 @@
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+use async_trait::async_trait;
+
+use crate::aggregate::Aggregate;
+use crate::event::EventEnvelope;
+use crate::query::{Query, Reactor};
+use crate::store::AggregateContext;
+
+/// A [Query] test double that records every `dispatch` call, so a test can assert it was
+/// invoked the expected number of times with a matching event batch, without writing a
+/// full fake view repository.
+///
+/// The expectation is verified when the `RecordingQuery` is dropped, mirroring the
+/// `mockall` set-expectation-then-verify-on-drop pattern.
+pub struct RecordingQuery<A: Aggregate> {
+    expected_calls: usize,
+    predicate: Box<dyn Fn(&str, &[EventEnvelope<A>]) -> bool + Send + Sync>,
+    calls: Mutex<usize>,
+}
+
+impl<A: Aggregate> RecordingQuery<A> {
+    /// Creates a `RecordingQuery` expecting exactly `expected_calls` dispatches, each of
+    /// which must satisfy `predicate`.
+    pub fn expect_calls(
+        expected_calls: usize,
+        predicate: impl Fn(&str, &[EventEnvelope<A>]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            expected_calls,
+            predicate: Box::new(predicate),
+            calls: Mutex::new(0),
+        }
+    }
+}
+
+#[async_trait]
+impl<A: Aggregate> Query<A> for RecordingQuery<A> {
+    async fn dispatch(&self, aggregate_id: &str, events: &[EventEnvelope<A>]) {
+        assert!(
+            (self.predicate)(aggregate_id, events),
+            "RecordingQuery dispatch did not match the expected predicate"
+        );
+        *self.calls.lock().unwrap() += 1;
+    }
+}
+
+impl<A: Aggregate> Drop for RecordingQuery<A> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            // A predicate assertion inside `dispatch` already failed and is unwinding;
+            // don't mask it with a second panic over a call count that never got the
+            // chance to reach `expected_calls`.
+            return;
+        }
+        let calls = *self.calls.lock().unwrap();
+        assert_eq!(
+            calls, self.expected_calls,
+            "RecordingQuery expected {} call(s) but received {calls}",
+            self.expected_calls
+        );
+    }
+}
+
+/// A [Reactor] test double that records every `react` call and returns a fixed batch of
+/// follow-up events, so a test can assert a saga issued the commands it expected without
+/// writing a full fake reactor.
+///
+/// The expectation is verified when the `RecordingReactor` is dropped.
+pub struct RecordingReactor<A, AC>
+where
+    A: Aggregate,
+    AC: AggregateContext<A>,
+{
+    expected_calls: usize,
+    follow_up_events: Vec<A::Event>,
+    predicate: Box<dyn Fn(&str, &[EventEnvelope<A>]) -> bool + Send + Sync>,
+    calls: Mutex<usize>,
+    _phantom: PhantomData<AC>,
+}
+
+impl<A, AC> RecordingReactor<A, AC>
+where
+    A: Aggregate,
+    AC: AggregateContext<A>,
+{
+    /// Creates a `RecordingReactor` expecting exactly `expected_calls` reactions, each of
+    /// which must satisfy `predicate`, returning `follow_up_events` from every `react` call.
+    pub fn expect_calls(
+        expected_calls: usize,
+        follow_up_events: Vec<A::Event>,
+        predicate: impl Fn(&str, &[EventEnvelope<A>]) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            expected_calls,
+            follow_up_events,
+            predicate: Box::new(predicate),
+            calls: Mutex::new(0),
+            _phantom: PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<A, AC> Reactor<A, AC> for RecordingReactor<A, AC>
+where
+    A: Aggregate,
+    AC: AggregateContext<A> + Send + Sync,
+{
+    async fn react(
+        &self,
+        _context: &AC,
+        aggregate_id: &str,
+        _services: &A::Services,
+        events: &[EventEnvelope<A>],
+    ) -> Result<Vec<A::Event>, A::Error> {
+        assert!(
+            (self.predicate)(aggregate_id, events),
+            "RecordingReactor react did not match the expected predicate"
+        );
+        *self.calls.lock().unwrap() += 1;
+        Ok(self.follow_up_events.clone())
+    }
+}
+
+impl<A, AC> Drop for RecordingReactor<A, AC>
+where
+    A: Aggregate,
+    AC: AggregateContext<A>,
+{
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            // A predicate assertion inside `react` already failed and is unwinding;
+            // don't mask it with a second panic over a call count that never got the
+            // chance to reach `expected_calls`.
+            return;
+        }
+        let calls = *self.calls.lock().unwrap();
+        assert_eq!(
+            calls, self.expected_calls,
+            "RecordingReactor expected {} call(s) but received {calls}",
+            self.expected_calls
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_fixtures::{envelope, TestAggregate, TestContext, TestEvent};
+
+    #[tokio::test]
+    async fn recording_query_counts_matching_dispatches() {
+        let recorder = RecordingQuery::<TestAggregate>::expect_calls(2, |id, events| {
+            id == "agg-1" && events.len() == 1
+        });
+        recorder
+            .dispatch(
+                "agg-1",
+                &[envelope("agg-1", 1, TestEvent::Recorded("a".into()))],
+            )
+            .await;
+        recorder
+            .dispatch(
+                "agg-1",
+                &[envelope("agg-1", 2, TestEvent::Recorded("b".into()))],
+            )
+            .await;
+        // Dropped here with exactly the expected 2 calls recorded; must not panic.
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "RecordingQuery expected 2 call(s) but received 1")]
+    async fn recording_query_panics_on_drop_when_expectation_unmet() {
+        let recorder = RecordingQuery::<TestAggregate>::expect_calls(2, |_, _| true);
+        recorder.dispatch("agg-1", &[]).await;
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "did not match the expected predicate")]
+    async fn recording_query_panics_on_predicate_mismatch_without_masking_it() {
+        let recorder = RecordingQuery::<TestAggregate>::expect_calls(1, |id, _| id == "other");
+        recorder.dispatch("agg-1", &[]).await;
+    }
+
+    #[tokio::test]
+    async fn recording_reactor_returns_its_configured_follow_up_events() {
+        let reactor = RecordingReactor::<TestAggregate, TestContext>::expect_calls(
+            1,
+            vec![TestEvent::Recorded("follow-up".into())],
+            |id, _| id == "agg-1",
+        );
+        let events = reactor
+            .react(
+                &TestContext,
+                "agg-1",
+                &(),
+                &[envelope("agg-1", 1, TestEvent::Recorded("a".into()))],
+            )
+            .await
+            .unwrap();
+        assert_eq!(events, vec![TestEvent::Recorded("follow-up".into())]);
+    }
+
+    #[tokio::test]
+    #[should_panic(expected = "RecordingReactor expected 1 call(s) but received 0")]
+    async fn recording_reactor_panics_on_drop_when_never_called() {
+        let _reactor =
+            RecordingReactor::<TestAggregate, TestContext>::expect_calls(1, vec![], |_, _| true);
+    }
+}
@@ -0,0 +1,63 @@
+#![cfg(test)]
+
+//! Minimal [Aggregate] fixtures shared by the unit tests in this crate.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::aggregate::Aggregate;
+use crate::event::EventEnvelope;
+use crate::store::AggregateContext;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) enum TestEvent {
+    Recorded(String),
+}
+
+#[derive(Debug)]
+pub(crate) struct TestError(pub String);
+
+impl std::fmt::Display for TestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl std::error::Error for TestError {}
+
+#[derive(Debug, Default)]
+pub(crate) struct TestAggregate;
+
+#[async_trait]
+impl Aggregate for TestAggregate {
+    type Command = ();
+    type Event = TestEvent;
+    type Error = TestError;
+    type Services = ();
+
+    async fn handle(&self, _command: (), _services: &()) -> Result<Vec<TestEvent>, TestError> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _event: TestEvent) {}
+}
+
+/// The [AggregateContext] fixture used by [`TestAggregate`]; this crate's series never
+/// reads anything off the context, so it carries no state of its own.
+#[derive(Debug, Default)]
+pub(crate) struct TestContext;
+
+impl AggregateContext<TestAggregate> for TestContext {}
+
+pub(crate) fn envelope(
+    aggregate_id: &str,
+    sequence: usize,
+    payload: TestEvent,
+) -> EventEnvelope<TestAggregate> {
+    EventEnvelope {
+        aggregate_id: aggregate_id.to_string(),
+        sequence,
+        payload,
+        metadata: Default::default(),
+    }
+}